@@ -1,6 +1,7 @@
 //! This low-level library reads the system timezone information files and returns a Tz struct representing the TZfile
 //! fields as described in the man page (<http://man7.org/linux/man-pages/man5/tzfile.5.html>).
-//! Only compatible with V1 (32 bits) format version for the moment.
+//! Supports the V1 (32 bits) format as well as the V2/V3 (64 bits) format used by files
+//! with transitions beyond 2038.
 //!
 //! For higher level parsing, see [my parsing library](https://github.com/nicolasbauw/rs-tzparse).
 //!
@@ -15,13 +16,13 @@
 //!     // Parses TZfile header
 //!     let header = Tzfile::parse_header(&buffer).unwrap();
 //!     // Parses file content
-//!     println!("{:?}", header.parse(&buffer));
+//!     println!("{:?}", header.parse(&buffer).unwrap());
 //! }
 //!```
 //!
 //! which outputs:
 //!
-//! Tz { tzh_timecnt_data: [1918-03-31T09:00:00Z, 1918-10-27T08:00:00Z, 1919-03-30T09:00:00Z, 1919-10-26T08:00:00Z, 1942-02-09T09:00:00Z, 1944-01-01T06:01:00Z, 1944-04-01T07:01:00Z, 1944-10-01T06:01:00Z, 1967-04-30T09:00:00Z, 1967-10-29T08:00:00Z], tzh_timecnt_indices: [0, 1, 0, 1, 2, 1, 2, 1, 0, 1], tzh_typecnt: [Ttinfo { tt_gmtoff: -21600, tt_isdst: 1, tt_abbrind: 0 }, Ttinfo { tt_gmtoff: -25200, tt_isdst: 0, tt_abbrind: 1 }, Ttinfo { tt_gmtoff: -21600, tt_isdst: 1, tt_abbrind: 2 }], tz_abbr: ["MDT", "MST", "MWT"] }
+//! Tz { version: 50, tzh_timecnt_data: [1883-11-18T19:00:00Z, 1918-03-31T09:00:00Z, 1918-10-27T08:00:00Z, 1919-03-30T09:00:00Z, 1919-10-26T08:00:00Z, 1942-02-09T09:00:00Z, 1944-01-01T06:01:00Z, 1944-04-01T07:01:00Z, 1944-10-01T06:01:00Z, 1967-04-30T09:00:00Z, 1967-10-29T08:00:00Z], tzh_timecnt_indices: [4, 1, 2, 1, 2, 3, 2, 3, 2, 1, 2], tzh_typecnt: [Ttinfo { tt_gmtoff: -26898, tt_isdst: 0, tt_abbrind: 0 }, Ttinfo { tt_gmtoff: -21600, tt_isdst: 1, tt_abbrind: 1 }, Ttinfo { tt_gmtoff: -25200, tt_isdst: 0, tt_abbrind: 2 }, Ttinfo { tt_gmtoff: -21600, tt_isdst: 1, tt_abbrind: 3 }, Ttinfo { tt_gmtoff: -25200, tt_isdst: 0, tt_abbrind: 2 }], tz_abbr: ["LMT", "MDT", "MST", "MWT"], tzh_leapcnt_data: [], tz_footer: Some(TransitionRule { std_abbr: "MST", std_offset: -25200, dst: None }) }
 //!
 //! It uses system TZfiles (default location on Linux and Macos /usr/share/zoneinfo). On Windows, default expected location is HOME/.zoneinfo. You can override the TZfiles default location with the TZFILES_DIR environment variable. Example for Windows:
 //!
@@ -30,17 +31,31 @@
 use dirs;
 use byteorder::{ByteOrder, BE};
 use chrono::prelude::*;
-use std::{env, error, fmt, fs::File, io::prelude::*, path::PathBuf, str::from_utf8};
+use std::{
+    collections::HashMap, env, error, fmt, fs, fs::File, io::prelude::*, path::Path,
+    path::PathBuf, str::from_utf8,
+};
 
 // TZif magic four bytes
 static MAGIC: u32 = 0x545A6966;
 // End of first (V1) header
 static V1_HEADER_END: usize = 0x2C;
+// Header size is the same for every version (V1 and V2/V3)
+static HEADER_LEN: usize = 0x2C;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Error {
     // Invalid file format.
     InvalidMagic,
+    // File is shorter than the header/counts it declares say it should be.
+    Truncated,
+    // An abbreviation or TZ footer string is not valid UTF-8.
+    InvalidUtf8,
+    // A `tt_abbrind` value points outside the abbreviation region.
+    InvalidIndex,
+    // The POSIX TZ footer string is malformed (e.g. an unterminated `<...>` name, a missing
+    // `,` between rules, or a non-numeric day/month/week field).
+    InvalidTzRule,
 }
 
 impl fmt::Display for Error {
@@ -48,6 +63,10 @@ impl fmt::Display for Error {
         f.write_str("tzfile error: ")?;
         f.write_str(match self {
             Error::InvalidMagic => "invalid TZfile",
+            Error::Truncated => "truncated TZfile",
+            Error::InvalidUtf8 => "invalid UTF-8 in TZfile",
+            Error::InvalidIndex => "invalid index in TZfile",
+            Error::InvalidTzRule => "invalid TZ rule in TZfile footer",
         })
     }
 }
@@ -62,10 +81,327 @@ impl From<Error> for std::io::Error {
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Tz<'a> {
+    // Version of the block the data below was decoded from ('\0' for V1, '2' or '3' for V2/V3)
+    pub version: u8,
     pub tzh_timecnt_data: Vec<DateTime<Utc>>,
     pub tzh_timecnt_indices: &'a [u8],
     pub tzh_typecnt: Vec<Ttinfo>,
     pub tz_abbr: Vec<&'a str>,
+    pub tzh_leapcnt_data: Vec<LeapSecond>,
+    // V2/V3 files end with a POSIX TZ string describing the rule in effect after the last
+    // recorded transition. V1 files have no footer.
+    pub tz_footer: Option<TransitionRule>,
+}
+
+// The resolved UTC offset and abbreviation in effect at a given instant, as returned by
+// `Tz::find`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Offset<'a> {
+    pub tt_gmtoff: isize,
+    pub tt_isdst: bool,
+    pub abbr: &'a str,
+}
+
+impl<'a> Tz<'a> {
+    // Resolves the UTC offset and abbreviation in effect at `at`, mirroring glibc's
+    // `__tzfile_compute`: binary-searches the recorded transitions for the last one at or
+    // before `at`, and once `at` is past the last recorded transition, falls back to the
+    // POSIX TZ footer's DST rule. Without a footer, or with a footer that has no DST rule, the
+    // type of the last recorded transition is used. Instants before the first transition (or
+    // a file with no transitions) get the first non-DST type.
+    pub fn find(&self, at: DateTime<Utc>) -> Offset<'_> {
+        if self.tzh_timecnt_data.is_empty() || at < self.tzh_timecnt_data[0] {
+            return self.first_std_offset();
+        }
+
+        let idx = self.tzh_timecnt_data.partition_point(|t| *t <= at) - 1;
+        if idx == self.tzh_timecnt_data.len() - 1 {
+            if let Some(footer) = &self.tz_footer {
+                if let Some(offset) = Tz::offset_from_footer(footer, at) {
+                    return offset;
+                }
+            }
+        }
+        self.offset_at_index(idx).unwrap_or_else(|| self.first_std_offset())
+    }
+
+    // `tzh_timecnt_indices` is raw file bytes never validated against `tzh_typecnt`'s length, so
+    // this returns `None` (rather than indexing out of bounds) for a type index that doesn't
+    // exist.
+    fn offset_at_index(&self, idx: usize) -> Option<Offset<'_>> {
+        let ttinfo = self
+            .tzh_typecnt
+            .get(self.tzh_timecnt_indices[idx] as usize)?;
+        Some(Offset {
+            tt_gmtoff: ttinfo.tt_gmtoff,
+            tt_isdst: ttinfo.tt_isdst != 0,
+            abbr: self.tz_abbr[ttinfo.tt_abbrind as usize],
+        })
+    }
+
+    fn first_std_offset(&self) -> Offset<'_> {
+        let ttinfo = self
+            .tzh_typecnt
+            .iter()
+            .find(|tti| tti.tt_isdst == 0)
+            .unwrap_or(&self.tzh_typecnt[0]);
+        Offset {
+            tt_gmtoff: ttinfo.tt_gmtoff,
+            tt_isdst: ttinfo.tt_isdst != 0,
+            abbr: self.tz_abbr[ttinfo.tt_abbrind as usize],
+        }
+    }
+
+    fn offset_from_footer(footer: &TransitionRule, at: DateTime<Utc>) -> Option<Offset<'_>> {
+        let dst = footer.dst.as_ref()?;
+        let (dst_start, dst_end) = footer.transitions(at.year())?;
+        // In the southern hemisphere DST spans the new year, so the start instant can be later
+        // in the year than the end instant; DST is then active outside of [start, end).
+        let is_dst = if dst_start <= dst_end {
+            at >= dst_start && at < dst_end
+        } else {
+            at >= dst_start || at < dst_end
+        };
+        Some(if is_dst {
+            Offset {
+                tt_gmtoff: dst.offset as isize,
+                tt_isdst: true,
+                abbr: &dst.abbr,
+            }
+        } else {
+            Offset {
+                tt_gmtoff: footer.std_offset as isize,
+                tt_isdst: false,
+                abbr: &footer.std_abbr,
+            }
+        })
+    }
+}
+
+// A leap-second record: the UTC instant the leap second takes effect, and the cumulative
+// TAI - UTC correction in effect from that instant on.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LeapSecond {
+    pub transition_time: i64,
+    pub correction: i32,
+}
+
+// A day-of-year designator as used in the POSIX TZ string start/end rules.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum RuleDay {
+    // `n`: 0-based day of year (0-365), Feb 29 is counted in leap years.
+    Julian0(u16),
+    // `Jn`: 1-based day of year (1-365), Feb 29 is never counted.
+    Julian1(u16),
+    // `Mm.w.d`: weekday `d` (0 = Sunday) of week `w` (1-5, 5 meaning "last") of month `m` (1-12).
+    MonthWeekDay { month: u8, week: u8, day: u8 },
+}
+
+// The DST portion of a POSIX TZ string: abbreviation, UTC offset, and the rules describing when
+// DST starts and ends.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DstRule {
+    pub abbr: String,
+    pub offset: i32,
+    pub start: RuleDay,
+    pub start_time: i32,
+    pub end: RuleDay,
+    pub end_time: i32,
+}
+
+// A parsed POSIX TZ string (the V2/V3 footer), describing the standard offset and, if the zone
+// observes DST, the rule used to compute transitions past the last one recorded in the file.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TransitionRule {
+    pub std_abbr: String,
+    pub std_offset: i32,
+    pub dst: Option<DstRule>,
+}
+
+impl TransitionRule {
+    // Parses a POSIX TZ string such as `MST7MDT,M3.2.0,M11.1.0` (the bytes between the two `\n`
+    // delimiters at the end of a V2/V3 file). Returns `Error::InvalidTzRule` for an unterminated
+    // `<...>` name, a missing `,` before the start or end rule, or a non-numeric rule day field.
+    // Other deviations are not rejected: a non-numeric offset or time value reads as 0, and any
+    // text after the end rule is ignored.
+    fn parse(s: &str) -> Result<TransitionRule, Error> {
+        let (std_abbr, rest) = take_tz_name(s)?;
+        let (std_offset, rest) = take_tz_offset(rest);
+        // tt_gmtoff / Ttinfo offsets are seconds east of UTC, but POSIX TZ offsets are given
+        // west of UTC, so we negate to keep a single sign convention across the crate.
+        let std_offset = -std_offset;
+
+        if rest.is_empty() {
+            return Ok(TransitionRule {
+                std_abbr,
+                std_offset,
+                dst: None,
+            });
+        }
+
+        let (dst_abbr, rest) = take_tz_name(rest)?;
+        let (dst_offset, rest) = if rest.starts_with(',') {
+            (std_offset + 3600, rest)
+        } else {
+            let (offset, rest) = take_tz_offset(rest);
+            (-offset, rest)
+        };
+
+        let rest = rest.strip_prefix(',').ok_or(Error::InvalidTzRule)?;
+        let (start, rest) = take_tz_rule_day(rest)?;
+        let (start_time, rest) = take_tz_rule_time(rest);
+        let rest = rest.strip_prefix(',').ok_or(Error::InvalidTzRule)?;
+        let (end, rest) = take_tz_rule_day(rest)?;
+        let (end_time, _) = take_tz_rule_time(rest);
+
+        Ok(TransitionRule {
+            std_abbr,
+            std_offset,
+            dst: Some(DstRule {
+                abbr: dst_abbr,
+                offset: dst_offset,
+                start,
+                start_time,
+                end,
+                end_time,
+            }),
+        })
+    }
+
+    // Computes the concrete (DST start, DST end) UTC instants this rule implies for `year`, or
+    // `None` if the zone doesn't observe DST. This is what glibc falls back to once a timestamp
+    // is past the last transition recorded in the TZif data.
+    pub fn transitions(&self, year: i32) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let dst = self.dst.as_ref()?;
+        // DST starts at `start_time` local standard time, and ends at `end_time` local DST time.
+        let start = rule_day_to_date(&dst.start, year).and_time(NaiveTime::from_hms(0, 0, 0))
+            + chrono::Duration::seconds((dst.start_time - self.std_offset) as i64);
+        let end = rule_day_to_date(&dst.end, year).and_time(NaiveTime::from_hms(0, 0, 0))
+            + chrono::Duration::seconds((dst.end_time - dst.offset) as i64);
+        Some((Utc.from_utc_datetime(&start), Utc.from_utc_datetime(&end)))
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+// Resolves a `RuleDay` to a calendar date for the given year.
+fn rule_day_to_date(day: &RuleDay, year: i32) -> NaiveDate {
+    match day {
+        // `Jn`: day of year, 1-365, Feb 29 is never counted.
+        RuleDay::Julian1(n) => {
+            let ordinal = if is_leap_year(year) && *n >= 60 {
+                *n as u32 + 1
+            } else {
+                *n as u32
+            };
+            NaiveDate::from_yo(year, ordinal)
+        }
+        // `n`: day of year, 0-365, Feb 29 is counted in leap years.
+        RuleDay::Julian0(n) => NaiveDate::from_yo(year, *n as u32 + 1),
+        // `Mm.w.d`: weekday `d` of week `w` of month `m` (week 5 means "last").
+        RuleDay::MonthWeekDay { month, week, day } => {
+            let first_of_month = NaiveDate::from_ymd(year, *month as u32, 1);
+            let first_weekday = first_of_month.weekday().num_days_from_sunday() as i64;
+            let mut day_of_month = 1 + (*day as i64 - first_weekday + 7) % 7 + (*week as i64 - 1) * 7;
+            let days_in_month = days_in_month(year, *month as u32);
+            if day_of_month > days_in_month as i64 {
+                day_of_month -= 7;
+            }
+            NaiveDate::from_ymd(year, *month as u32, day_of_month as u32)
+        }
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_month_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd(next_month_year, next_month, 1)
+        .signed_duration_since(NaiveDate::from_ymd(year, month, 1))
+        .num_days() as u32
+}
+
+// Leading letters (`EST`) or a `<...>` quoted name (`<-03>`), as used for TZ abbreviations.
+fn take_tz_name(s: &str) -> Result<(String, &str), Error> {
+    if let Some(rest) = s.strip_prefix('<') {
+        let end = rest.find('>').ok_or(Error::InvalidTzRule)?;
+        Ok((rest[..end].to_string(), &rest[end + 1..]))
+    } else {
+        let end = s
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(s.len());
+        Ok((s[..end].to_string(), &s[end..]))
+    }
+}
+
+// `[+-]hh[:mm[:ss]]`, returned as a signed number of seconds.
+fn take_tz_offset(s: &str) -> (i32, &str) {
+    let (sign, s) = match s.as_bytes().first() {
+        Some(b'+') => (1, &s[1..]),
+        Some(b'-') => (-1, &s[1..]),
+        _ => (1, s),
+    };
+    let end = s
+        .find(|c: char| !(c.is_ascii_digit() || c == ':'))
+        .unwrap_or(s.len());
+    let (field, rest) = (&s[..end], &s[end..]);
+    let mut parts = field.split(':');
+    let hours: i32 = parts.next().unwrap_or("0").parse().unwrap_or(0);
+    let minutes: i32 = parts.next().unwrap_or("0").parse().unwrap_or(0);
+    let seconds: i32 = parts.next().unwrap_or("0").parse().unwrap_or(0);
+    (sign * (hours * 3600 + minutes * 60 + seconds), rest)
+}
+
+// `Mm.w.d`, `Jn` or `n`.
+fn take_tz_rule_day(s: &str) -> Result<(RuleDay, &str), Error> {
+    if let Some(rest) = s.strip_prefix('M') {
+        // Stop at the next rule (`,`) or at this rule's own `/HH:MM:SS` suffix: the last rule
+        // in a footer has no trailing comma, so `M10.5.0/3` would otherwise have `/3` parsed
+        // as part of the day field.
+        let end = rest.find([',', '/']).unwrap_or(rest.len());
+        let (field, rest) = (&rest[..end], &rest[end..]);
+        let mut it = field.split('.');
+        let month: u8 = it
+            .next()
+            .ok_or(Error::InvalidTzRule)?
+            .parse()
+            .map_err(|_| Error::InvalidTzRule)?;
+        let week: u8 = it
+            .next()
+            .ok_or(Error::InvalidTzRule)?
+            .parse()
+            .map_err(|_| Error::InvalidTzRule)?;
+        let day: u8 = it
+            .next()
+            .ok_or(Error::InvalidTzRule)?
+            .parse()
+            .map_err(|_| Error::InvalidTzRule)?;
+        Ok((RuleDay::MonthWeekDay { month, week, day }, rest))
+    } else if let Some(rest) = s.strip_prefix('J') {
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let n: u16 = rest[..end].parse().map_err(|_| Error::InvalidTzRule)?;
+        Ok((RuleDay::Julian1(n), &rest[end..]))
+    } else {
+        let end = s
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(s.len());
+        let n: u16 = s[..end].parse().map_err(|_| Error::InvalidTzRule)?;
+        Ok((RuleDay::Julian0(n), &s[end..]))
+    }
+}
+
+// Optional `/HH:MM:SS` time-of-day suffix, defaulting to 02:00:00 (7200s) per POSIX.
+fn take_tz_rule_time(s: &str) -> (i32, &str) {
+    match s.strip_prefix('/') {
+        Some(rest) => take_tz_offset(rest),
+        None => (7200, s),
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -89,79 +425,231 @@ pub struct Tzfile {
 
 impl Tzfile {
     pub fn parse_header(buffer: &[u8]) -> Result<Tzfile, Error> {
-        let magic = BE::read_u32(&buffer[0x00..=0x03]);
+        Tzfile::parse_header_at(buffer, 0)
+    }
+
+    // Parses a 44-byte TZif header located at `offset` in buffer. Used both for the V1 header
+    // (offset 0) and, on V2/V3 files, for the second header following the V1 data block.
+    fn parse_header_at(buffer: &[u8], offset: usize) -> Result<Tzfile, Error> {
+        if buffer.len() < offset + HEADER_LEN {
+            return Err(Error::Truncated);
+        }
+        let magic = BE::read_u32(&buffer[offset..offset + 4]);
         if magic != MAGIC {
             return Err(Error::InvalidMagic);
         }
         Ok(Tzfile {
             magic: magic,
-            version: buffer[4],
-            tzh_ttisgmtcnt: BE::read_i32(&buffer[0x14..=0x17]) as usize,
-            tzh_ttisstdcnt: BE::read_i32(&buffer[0x18..=0x1B]) as usize,
-            tzh_leapcnt: BE::read_i32(&buffer[0x1C..=0x1F]) as usize,
-            tzh_timecnt: BE::read_i32(&buffer[0x20..=0x23]) as usize,
-            tzh_typecnt: BE::read_i32(&buffer[0x24..=0x27]) as usize,
-            tzh_charcnt: BE::read_i32(&buffer[0x28..=0x2b]) as usize,
+            version: buffer[offset + 4],
+            tzh_ttisgmtcnt: BE::read_i32(&buffer[offset + 0x14..offset + 0x18]) as usize,
+            tzh_ttisstdcnt: BE::read_i32(&buffer[offset + 0x18..offset + 0x1C]) as usize,
+            tzh_leapcnt: BE::read_i32(&buffer[offset + 0x1C..offset + 0x20]) as usize,
+            tzh_timecnt: BE::read_i32(&buffer[offset + 0x20..offset + 0x24]) as usize,
+            tzh_typecnt: BE::read_i32(&buffer[offset + 0x24..offset + 0x28]) as usize,
+            tzh_charcnt: BE::read_i32(&buffer[offset + 0x28..offset + 0x2c]) as usize,
         })
     }
 
-    pub fn parse<'a>(&self, buffer: &'a [u8]) -> Tz<'a> {
-        // Calculates fields lengths and indexes (Version 1 format)
-        let tzh_timecnt_len: usize = self.tzh_timecnt * 5;
-        let tzh_typecnt_len: usize = self.tzh_typecnt * 6;
-        let tzh_leapcnt_len: usize = self.tzh_leapcnt * 4;
-        let tzh_charcnt_len: usize = self.tzh_charcnt;
-        let tzh_timecnt_end: usize = V1_HEADER_END + tzh_timecnt_len;
-        let tzh_typecnt_end: usize = tzh_timecnt_end + tzh_typecnt_len;
-        let tzh_leapcnt_end: usize = tzh_typecnt_end + tzh_leapcnt_len;
-        let tzh_charcnt_end: usize = tzh_leapcnt_end + tzh_charcnt_len;
+    // Length, in bytes, of the V1 (32-bit) data block following this header: transition times
+    // and indices, ttinfo structs, time zone abbreviations, leap-second records, and the
+    // standard/wall and UT/local indicators. `None` means the declared counts can't possibly
+    // fit in memory/the file and the caller should treat the file as truncated.
+    fn v1_data_len(&self) -> Option<usize> {
+        self.tzh_timecnt
+            .checked_mul(5)?
+            .checked_add(self.tzh_typecnt.checked_mul(6)?)?
+            .checked_add(self.tzh_leapcnt.checked_mul(8)?)?
+            .checked_add(self.tzh_charcnt)?
+            .checked_add(self.tzh_ttisstdcnt)?
+            .checked_add(self.tzh_ttisgmtcnt)
+    }
+
+    pub fn parse<'a>(&self, buffer: &'a [u8]) -> Result<Tz<'a>, Error> {
+        match self.version {
+            b'2' | b'3' => {
+                // V2/V3 files repeat the header and data block using 64-bit transition times.
+                // The V1 block is kept only for compatibility with pre-2038 readers, so we skip
+                // straight past it.
+                let v2_header_start = V1_HEADER_END
+                    .checked_add(self.v1_data_len().ok_or(Error::Truncated)?)
+                    .ok_or(Error::Truncated)?;
+                let header = Tzfile::parse_header_at(buffer, v2_header_start)?;
+                let (mut tz, block_end) = header.decode_block(buffer, v2_header_start + HEADER_LEN, 8)?;
+                // The V2/V3 data block is followed by a newline-delimited POSIX TZ string
+                // describing the rule in effect after the last recorded transition.
+                let footer_start = block_end + 1;
+                if footer_start > buffer.len() {
+                    return Err(Error::Truncated);
+                }
+                let footer_len = buffer[footer_start..]
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .ok_or(Error::Truncated)?;
+                let footer = from_utf8(&buffer[footer_start..footer_start + footer_len])
+                    .map_err(|_| Error::InvalidUtf8)?;
+                tz.tz_footer = if footer.is_empty() {
+                    None
+                } else {
+                    Some(TransitionRule::parse(footer)?)
+                };
+                Ok(tz)
+            }
+            _ => Ok(self.decode_block(buffer, V1_HEADER_END, 4)?.0),
+        }
+    }
+
+    // Decodes a data block (transition times, indices, ttinfo structs and abbreviations) starting
+    // at `start`, using `time_size` bytes (4 for V1, 8 for V2/V3) per transition time. Returns the
+    // decoded `Tz` along with the offset right after the block (isstd/isgmt indicators included),
+    // which for V2/V3 files is where the POSIX TZ footer starts. All field lengths are checked
+    // against `buffer`'s actual size before any indexing, so a truncated or hostile file yields
+    // an `Error` instead of a panic.
+    fn decode_block<'a>(
+        &self,
+        buffer: &'a [u8],
+        start: usize,
+        time_size: usize,
+    ) -> Result<(Tz<'a>, usize), Error> {
+        // Calculates fields lengths and indexes using u64 arithmetic so that bogus (e.g.
+        // negative, once reinterpreted as usize) counts can't overflow instead of being caught
+        // below. Layout per tzfile(5): transition times/types, ttinfo structs, abbreviation
+        // chars, leap-second records, then the isstd/isgmt indicators.
+        let time_size64 = time_size as u64;
+        let tzh_timecnt_len = (self.tzh_timecnt as u64)
+            .checked_mul(time_size64 + 1)
+            .ok_or(Error::Truncated)?;
+        let tzh_typecnt_len = (self.tzh_typecnt as u64)
+            .checked_mul(6)
+            .ok_or(Error::Truncated)?;
+        let tzh_charcnt_len = self.tzh_charcnt as u64;
+        let tzh_leapcnt_len = (self.tzh_leapcnt as u64)
+            .checked_mul(time_size64 + 4)
+            .ok_or(Error::Truncated)?;
+        let tzh_timecnt_end = (start as u64)
+            .checked_add(tzh_timecnt_len)
+            .ok_or(Error::Truncated)?;
+        let tzh_typecnt_end = tzh_timecnt_end
+            .checked_add(tzh_typecnt_len)
+            .ok_or(Error::Truncated)?;
+        let tzh_charcnt_end = tzh_typecnt_end
+            .checked_add(tzh_charcnt_len)
+            .ok_or(Error::Truncated)?;
+        let tzh_leapcnt_end = tzh_charcnt_end
+            .checked_add(tzh_leapcnt_len)
+            .ok_or(Error::Truncated)?;
+        let block_end = tzh_leapcnt_end
+            .checked_add(self.tzh_ttisstdcnt as u64)
+            .and_then(|n| n.checked_add(self.tzh_ttisgmtcnt as u64))
+            .ok_or(Error::Truncated)?;
+        if block_end > buffer.len() as u64 {
+            return Err(Error::Truncated);
+        }
+        // All offsets are now known to fit within buffer, so the casts back to usize are safe.
+        let tzh_timecnt_end = tzh_timecnt_end as usize;
+        let tzh_typecnt_end = tzh_typecnt_end as usize;
+        let tzh_charcnt_end = tzh_charcnt_end as usize;
+        let tzh_leapcnt_end = tzh_leapcnt_end as usize;
+        let block_end = block_end as usize;
 
         // Extracting data fields
         let tzh_timecnt_data: Vec<DateTime<Utc>> = buffer
-            [V1_HEADER_END..V1_HEADER_END + self.tzh_timecnt * 4]
-            .chunks_exact(4)
-            .map(|tt| Utc.timestamp(BE::read_i32(tt).into(), 0))
+            [start..start + self.tzh_timecnt * time_size]
+            .chunks_exact(time_size)
+            .map(|tt| {
+                let secs = if time_size == 8 {
+                    BE::read_i64(tt)
+                } else {
+                    BE::read_i32(tt).into()
+                };
+                Utc.timestamp(secs, 0)
+            })
             .collect();
 
         let tzh_timecnt_indices: &[u8] =
-            &buffer[V1_HEADER_END + self.tzh_timecnt * 4..tzh_timecnt_end];
+            &buffer[start + self.tzh_timecnt * time_size..tzh_timecnt_end];
+
+        let abbr_bytes = &buffer[tzh_typecnt_end..tzh_charcnt_end];
+        let mut tz_abbr: Vec<&str> = from_utf8(abbr_bytes)
+            .map_err(|_| Error::InvalidUtf8)?
+            .split("\u{0}")
+            .collect();
+        // Removes last empty string
+        tz_abbr.pop();
+
+        // `tti[5]` is a *byte* offset into the (NUL-separated) abbreviation character array, not
+        // an index into `tz_abbr`. Most files point it at the start of one of those NUL-separated
+        // strings, but some (e.g. America/Adak) save space by also pointing it mid-string to
+        // reuse a shared suffix (`AHST\0` doubling as `HST` from offset+1), so a start-of-string
+        // offset map isn't enough -- any offset that doesn't land on one is resolved by slicing
+        // out its own NUL-terminated string and appending it to `tz_abbr`.
+        let mut abbr_index_by_offset: HashMap<usize, u8> = HashMap::new();
+        let mut offset = 0usize;
+        for (i, abbr) in tz_abbr.iter().enumerate() {
+            abbr_index_by_offset.insert(offset, i as u8);
+            offset += abbr.len() + 1;
+        }
 
-        let tzh_typecnt: Vec<Ttinfo> = buffer[tzh_timecnt_end..tzh_typecnt_end]
-            .chunks_exact(6)
-            .map(|tti| Ttinfo {
+        let mut tzh_typecnt: Vec<Ttinfo> = Vec::with_capacity(self.tzh_typecnt);
+        for tti in buffer[tzh_timecnt_end..tzh_typecnt_end].chunks_exact(6) {
+            let abbrind = tti[5] as usize;
+            if abbrind >= self.tzh_charcnt {
+                return Err(Error::InvalidIndex);
+            }
+            let tt_abbrind = match abbr_index_by_offset.get(&abbrind) {
+                Some(&i) => i,
+                None => {
+                    let len = abbr_bytes[abbrind..]
+                        .iter()
+                        .position(|&b| b == 0)
+                        .ok_or(Error::InvalidIndex)?;
+                    let abbr =
+                        from_utf8(&abbr_bytes[abbrind..abbrind + len]).map_err(|_| Error::InvalidUtf8)?;
+                    if tz_abbr.len() > u8::MAX as usize {
+                        return Err(Error::InvalidIndex);
+                    }
+                    let i = tz_abbr.len() as u8;
+                    tz_abbr.push(abbr);
+                    abbr_index_by_offset.insert(abbrind, i);
+                    i
+                }
+            };
+            tzh_typecnt.push(Ttinfo {
                 tt_gmtoff: BE::read_i32(&tti[0..4]) as isize,
                 tt_isdst: tti[4],
-                tt_abbrind: tti[5] / 4,
-            })
-            .collect();
+                tt_abbrind,
+            });
+        }
 
-        let mut tz_abbr: Vec<&str> = from_utf8(&buffer[tzh_leapcnt_end..tzh_charcnt_end])
-            .unwrap()
-            .split("\u{0}")
+        let tzh_leapcnt_data: Vec<LeapSecond> = buffer[tzh_charcnt_end..tzh_leapcnt_end]
+            .chunks_exact(time_size + 4)
+            .map(|rec| {
+                let (time_bytes, correction_bytes) = rec.split_at(time_size);
+                let transition_time = if time_size == 8 {
+                    BE::read_i64(time_bytes)
+                } else {
+                    BE::read_i32(time_bytes).into()
+                };
+                LeapSecond {
+                    transition_time,
+                    correction: BE::read_i32(correction_bytes),
+                }
+            })
             .collect();
-        // Removes last empty string
-        tz_abbr.pop().unwrap();
 
-        Tz {
+        let tz = Tz {
+            version: self.version,
             tzh_timecnt_data: tzh_timecnt_data,
             tzh_timecnt_indices: tzh_timecnt_indices,
             tzh_typecnt: tzh_typecnt,
+            tzh_leapcnt_data: tzh_leapcnt_data,
             tz_abbr: tz_abbr,
-        }
+            tz_footer: None,
+        };
+        Ok((tz, block_end))
     }
 
     pub fn read(tz: &str) -> Result<Vec<u8>, std::io::Error> {
-        let mut tz_files_root = if cfg!(windows) && env::var_os("TZFILES_DIR").is_none() {
-            // Default TZ files location (windows) is HOME/.zoneinfo, can be overridden by ENV
-            let mut d = dirs::home_dir().unwrap();
-            d.push(".zoneinfo");
-            d
-        } else {
-            // ENV overrides default directory, or defaults to /usr/share/zoneinfo (Linux / MacOS)
-            let mut d = PathBuf::new();
-            d.push(env::var("TZFILES_DIR").unwrap_or(format!("/usr/share/zoneinfo/")));
-            d
-        };
+        let mut tz_files_root = zoneinfo_root();
         tz_files_root.push(tz);
         let mut f = File::open(tz_files_root)?;
         let mut buffer = Vec::new();
@@ -170,6 +658,99 @@ impl Tzfile {
     }
 }
 
+// Root directory TZfiles are read from: HOME/.zoneinfo on Windows, /usr/share/zoneinfo on
+// Linux / MacOS, or the TZFILES_DIR environment variable if set.
+fn zoneinfo_root() -> PathBuf {
+    if cfg!(windows) && env::var_os("TZFILES_DIR").is_none() {
+        let mut d = dirs::home_dir().unwrap();
+        d.push(".zoneinfo");
+        d
+    } else {
+        let mut d = PathBuf::new();
+        d.push(env::var("TZFILES_DIR").unwrap_or(format!("/usr/share/zoneinfo/")));
+        d
+    }
+}
+
+// A reusable handle onto a zoneinfo directory: discovers the zones available under it, and
+// caches each zone's raw TZfile bytes so repeated lookups for the same zone skip re-reading
+// the file (the `Tz` is still re-parsed from the cached buffer on every call, since it borrows
+// from it).
+//
+// Note: this only avoids the disk read, not the parse. Caching the parsed `Tz` itself would
+// need it to own its data instead of borrowing `&'a [u8]` from the cached buffer -- a real
+// structural change, not done here -- so callers that call `get` for the same zone in a hot
+// loop are still paying for re-parsing each time.
+pub struct TzDb {
+    root: PathBuf,
+    cache: HashMap<String, Vec<u8>>,
+}
+
+impl TzDb {
+    // Opens a handle onto the zoneinfo root (honoring TZFILES_DIR, see `zoneinfo_root`).
+    pub fn new() -> TzDb {
+        TzDb {
+            root: zoneinfo_root(),
+            cache: HashMap::new(),
+        }
+    }
+
+    // Recursively lists the zone names available under the zoneinfo root (e.g. "Europe/Paris"),
+    // skipping the `posix` and `right` subtrees (alternate encodings of the same zones) and the
+    // `tzdata.zi`/`leapseconds` index files. Every other regular file is listed without checking
+    // that it is a TZfile.
+    pub fn list(&self) -> Vec<String> {
+        let mut zones = Vec::new();
+        TzDb::walk(&self.root, &self.root, &mut zones);
+        zones.sort();
+        zones
+    }
+
+    fn walk(root: &Path, dir: &Path, zones: &mut Vec<String>) {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if path.is_dir() {
+                if dir == root && (name == "posix" || name == "right") {
+                    continue;
+                }
+                TzDb::walk(root, &path, zones);
+            } else if name != "tzdata.zi" && name != "leapseconds" {
+                if let Ok(zone) = path.strip_prefix(root) {
+                    zones.push(zone.to_string_lossy().replace(std::path::MAIN_SEPARATOR, "/"));
+                }
+            }
+        }
+    }
+
+    // Parses the TZfile for `zone` (e.g. "Europe/Paris"), caching the file's bytes so repeated
+    // lookups reuse the same buffer instead of reading the file again.
+    pub fn get(&mut self, zone: &str) -> Result<Tz<'_>, std::io::Error> {
+        if !self.cache.contains_key(zone) {
+            let mut path = self.root.clone();
+            path.push(zone);
+            let mut f = File::open(path)?;
+            let mut buffer = Vec::new();
+            f.read_to_end(&mut buffer)?;
+            self.cache.insert(zone.to_string(), buffer);
+        }
+        let buffer = &self.cache[zone];
+        let header = Tzfile::parse_header(buffer)?;
+        Ok(header.parse(buffer)?)
+    }
+}
+
+impl Default for TzDb {
+    fn default() -> TzDb {
+        TzDb::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,7 +763,7 @@ mod tests {
     #[test]
     fn parse_header() {
         let buffer = Tzfile::read("America/Phoenix").unwrap();
-        let amph = Tzfile { magic: 1415211366, version: 50, tzh_ttisgmtcnt: 3, tzh_ttisstdcnt: 3, tzh_leapcnt: 0, tzh_timecnt: 10, tzh_typecnt: 3, tzh_charcnt: 12 };
+        let amph = Tzfile { magic: 1415211366, version: 50, tzh_ttisgmtcnt: 5, tzh_ttisstdcnt: 5, tzh_leapcnt: 0, tzh_timecnt: 11, tzh_typecnt: 5, tzh_charcnt: 16 };
         assert_eq!(Tzfile::parse_header(&buffer).unwrap(), amph);
     }
 
@@ -190,8 +771,8 @@ mod tests {
     fn parse_indices() {
         let buffer = Tzfile::read("America/Phoenix").unwrap();
         let header = Tzfile::parse_header(&buffer).unwrap();
-        let amph: [u8; 10] = [0, 1, 0, 1, 2, 1, 2, 1, 0, 1];
-        assert_eq!(header.parse(&buffer).tzh_timecnt_indices, amph);
+        let amph: [u8; 11] = [4, 1, 2, 1, 2, 3, 2, 3, 2, 1, 2];
+        assert_eq!(header.parse(&buffer).unwrap().tzh_timecnt_indices, amph);
     }
 
     #[test]
@@ -199,6 +780,7 @@ mod tests {
         let buffer = Tzfile::read("America/Phoenix").unwrap();
         let header = Tzfile::parse_header(&buffer).unwrap();
         let amph: Vec<DateTime<Utc>> = vec![
+            Utc.ymd(1883, 11, 18).and_hms(19, 0, 0),
             Utc.ymd(1918, 3, 31).and_hms(9, 0, 0),
             Utc.ymd(1918, 10, 27).and_hms(8, 0, 0),
             Utc.ymd(1919, 3, 30).and_hms(9, 0, 0),
@@ -209,15 +791,180 @@ mod tests {
             Utc.ymd(1944, 10, 1).and_hms(6, 1, 0),
             Utc.ymd(1967, 4, 30).and_hms(9, 0, 0),
             Utc.ymd(1967, 10, 29).and_hms(8, 0, 0)];
-        assert_eq!(header.parse(&buffer).tzh_timecnt_data, amph);
+        assert_eq!(header.parse(&buffer).unwrap().tzh_timecnt_data, amph);
+    }
+
+    #[test]
+    fn parse_version() {
+        let buffer = Tzfile::read("America/Phoenix").unwrap();
+        let header = Tzfile::parse_header(&buffer).unwrap();
+        assert_eq!(header.parse(&buffer).unwrap().version, header.version);
+    }
+
+    #[test]
+    fn parse_leap_seconds() {
+        let buffer = Tzfile::read("right/UTC").unwrap();
+        let header = Tzfile::parse_header(&buffer).unwrap();
+        let leap = header.parse(&buffer).unwrap().tzh_leapcnt_data;
+        assert_eq!(leap.len(), 27);
+        assert_eq!(
+            leap[0],
+            LeapSecond {
+                transition_time: 78796800,
+                correction: 1
+            }
+        );
+        assert_eq!(
+            leap[26],
+            LeapSecond {
+                transition_time: 1483228826,
+                correction: 27
+            }
+        );
+    }
+
+    #[test]
+    fn parse_tz_footer() {
+        let buffer = Tzfile::read("America/Phoenix").unwrap();
+        let header = Tzfile::parse_header(&buffer).unwrap();
+        // Phoenix does not observe DST, so the footer has no DST rule.
+        let footer = header.parse(&buffer).unwrap().tz_footer.unwrap();
+        assert_eq!(footer.std_abbr, "MST");
+        assert_eq!(footer.dst, None);
+    }
+
+    #[test]
+    fn tz_footer_dst_transitions() {
+        let rule = TransitionRule::parse("MST7MDT,M3.2.0,M11.1.0").unwrap();
+        let (start, end) = rule.transitions(2023).unwrap();
+        assert_eq!(start, Utc.ymd(2023, 3, 12).and_hms(9, 0, 0));
+        assert_eq!(end, Utc.ymd(2023, 11, 5).and_hms(8, 0, 0));
+    }
+
+    #[test]
+    fn tz_footer_rule_day_with_end_time_suffix() {
+        // The last rule in a footer has no trailing comma, so its optional `/HH:MM:SS` suffix
+        // is glued directly onto the day field, e.g. `M10.5.0/3` (as seen in the real `CET`
+        // zone). The day field must stop at the `/`, not swallow the suffix as part of the day.
+        let rule = TransitionRule::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        let dst = rule.dst.unwrap();
+        assert_eq!(
+            dst.end,
+            RuleDay::MonthWeekDay {
+                month: 10,
+                week: 5,
+                day: 0
+            }
+        );
+        assert_eq!(dst.end_time, 3 * 3600);
+    }
+
+    #[test]
+    fn parse_ttinfo_with_variable_length_abbreviations() {
+        // Pacific/Chatham's abbreviations ("LMT", "+1215", "+1345", "+1245") aren't all the same
+        // length, so a Ttinfo's tt_abbrind (a byte offset into the character array) can't be
+        // turned into a tz_abbr index by a fixed-width division.
+        let buffer = Tzfile::read("Pacific/Chatham").unwrap();
+        let header = Tzfile::parse_header(&buffer).unwrap();
+        let tz = header.parse(&buffer).unwrap();
+        assert_eq!(tz.tz_abbr, vec!["LMT", "+1215", "+1345", "+1245"]);
+        tz.find(Utc::now());
+    }
+
+    #[test]
+    fn parse_ttinfo_with_shared_abbreviation_suffix() {
+        // America/Adak reuses the tail of "AHST" as the separate abbreviation "HST" by pointing
+        // a Ttinfo's tt_abbrind at a byte offset one past the start of "AHST" rather than at the
+        // start of its own NUL-terminated string.
+        let buffer = Tzfile::read("America/Adak").unwrap();
+        let header = Tzfile::parse_header(&buffer).unwrap();
+        let tz = header.parse(&buffer).unwrap();
+        assert!(tz.tz_abbr.contains(&"HST"));
+        tz.find(Utc::now());
+    }
+
+    #[test]
+    fn parse_malformed_tz_rule() {
+        // A DST abbreviation with no start/end rule after it (as would result from a footer
+        // truncated or corrupted after the dst offset) is missing the `,` separator that must
+        // precede the start rule, and must be reported rather than panicking.
+        assert_eq!(
+            TransitionRule::parse("MST7MDT"),
+            Err(Error::InvalidTzRule)
+        );
+    }
+
+    #[test]
+    fn parse_truncated_header() {
+        let buffer = Tzfile::read("America/Phoenix").unwrap();
+        assert_eq!(
+            Tzfile::parse_header(&buffer[..10]),
+            Err(Error::Truncated)
+        );
+    }
+
+    #[test]
+    fn parse_truncated_data_block() {
+        let buffer = Tzfile::read("America/Phoenix").unwrap();
+        let header = Tzfile::parse_header(&buffer).unwrap();
+        assert_eq!(header.parse(&buffer[..50]), Err(Error::Truncated));
     }
 
     #[test]
     fn parse_ttgmtoff() {
         let buffer = Tzfile::read("America/Phoenix").unwrap();
         let header = Tzfile::parse_header(&buffer).unwrap();
-        let amph: [isize; 3] = [-21600, -25200, -21600];
-        let c: [isize; 3] = [header.parse(&buffer).tzh_typecnt[0].tt_gmtoff, header.parse(&buffer).tzh_typecnt[1].tt_gmtoff, header.parse(&buffer).tzh_typecnt[2].tt_gmtoff];
+        let amph: [isize; 5] = [-26898, -21600, -25200, -21600, -25200];
+        let c: [isize; 5] = [
+            header.parse(&buffer).unwrap().tzh_typecnt[0].tt_gmtoff,
+            header.parse(&buffer).unwrap().tzh_typecnt[1].tt_gmtoff,
+            header.parse(&buffer).unwrap().tzh_typecnt[2].tt_gmtoff,
+            header.parse(&buffer).unwrap().tzh_typecnt[3].tt_gmtoff,
+            header.parse(&buffer).unwrap().tzh_typecnt[4].tt_gmtoff,
+        ];
         assert_eq!(c, amph);
     }
+
+    #[test]
+    fn find_offset_from_recorded_transition() {
+        let buffer = Tzfile::read("America/Phoenix").unwrap();
+        let header = Tzfile::parse_header(&buffer).unwrap();
+        let tz = header.parse(&buffer).unwrap();
+        let offset = tz.find(Utc.ymd(1970, 1, 1).and_hms(0, 0, 0));
+        assert_eq!(offset.tt_gmtoff, -25200);
+        assert_eq!(offset.abbr, "MST");
+        assert_eq!(offset.tt_isdst, false);
+    }
+
+    #[test]
+    fn find_offset_from_tz_footer() {
+        let buffer = Tzfile::read("America/New_York").unwrap();
+        let header = Tzfile::parse_header(&buffer).unwrap();
+        let tz = header.parse(&buffer).unwrap();
+        // Far enough in the future to be past the last recorded transition.
+        let summer = tz.find(Utc.ymd(2100, 7, 1).and_hms(12, 0, 0));
+        assert_eq!(summer.abbr, "EDT");
+        assert_eq!(summer.tt_isdst, true);
+        let winter = tz.find(Utc.ymd(2100, 1, 1).and_hms(12, 0, 0));
+        assert_eq!(winter.abbr, "EST");
+        assert_eq!(winter.tt_isdst, false);
+    }
+
+    #[test]
+    fn tzdb_list() {
+        let db = TzDb::new();
+        let zones = db.list();
+        assert!(zones.contains(&"America/Phoenix".to_string()));
+        assert!(!zones.iter().any(|z| z.starts_with("posix/")));
+        assert!(!zones.iter().any(|z| z.starts_with("right/")));
+    }
+
+    #[test]
+    fn tzdb_get_caches_buffer() {
+        let mut db = TzDb::new();
+        assert_eq!(db.get("America/Phoenix").unwrap().tz_abbr, vec!["LMT", "MDT", "MST", "MWT"]);
+        assert!(db.cache.contains_key("America/Phoenix"));
+        // Second lookup reuses the cached buffer instead of re-reading the file.
+        assert_eq!(db.get("America/Phoenix").unwrap().tz_abbr, vec!["LMT", "MDT", "MST", "MWT"]);
+    }
 }
\ No newline at end of file